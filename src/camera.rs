@@ -1,15 +1,55 @@
-use crate::{Momentum, Movement, Player, PlayerAction};
+use crate::{Grounded, Momentum, Movement, Player, PlayerAction, PlayerValuesState};
+use bevy::input::mouse::{MouseMotion, MouseWheel};
 use bevy::prelude::*;
+use bevy::window::CursorGrabMode;
 use bevy_rapier3d::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
 
 #[derive(Component)]
 pub struct MainCamera;
 
+#[derive(Clone, Copy)]
 pub enum CameraMode {
     Normal,
     Fixed { position: Vec3, look_target: Vec3 },
+    Orbit {
+        yaw: f32,
+        pitch: f32,
+        sensitivity: f32,
+    },
 }
+
+pub enum AdjustTarget {
+    Zoom,
+    Easing,
+    Height,
+    Sensitivity,
+}
+
+impl AdjustTarget {
+    pub fn next(&self) -> Self {
+        match self {
+            AdjustTarget::Zoom => AdjustTarget::Easing,
+            AdjustTarget::Easing => AdjustTarget::Height,
+            AdjustTarget::Height => AdjustTarget::Sensitivity,
+            AdjustTarget::Sensitivity => AdjustTarget::Zoom,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct CameraConfig {
+    pub adjusting: AdjustTarget,
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        CameraConfig {
+            adjusting: AdjustTarget::Zoom,
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct CameraController {
     pub z_distance: f32,
@@ -19,7 +59,15 @@ pub struct CameraController {
     pub target_position: Vec3,
     pub player_position: Vec3,
     pub mode: CameraMode,
+    pub presets: Vec<CameraMode>,
+    pub preset_index: usize,
     pub blocked_by_a_wall: bool,
+    pub base_fov: f32,
+    pub max_fov: f32,
+    pub fov_momentum_range: (f32, f32),
+    pub bob_phase: f32,
+    pub bob_amplitude: Vec2,
+    pub bob_enabled: bool,
 }
 
 impl CameraController {
@@ -52,12 +100,32 @@ impl CameraController {
                 position: _,
                 look_target: _,
             } => self.easing * 5.0,
+            CameraMode::Orbit { .. } => self.easing,
         }
     }
+
+    pub fn desired_fov(&self, momentum: f32) -> f32 {
+        let (start, end) = self.fov_momentum_range;
+        let t = ((momentum - start) / (end - start)).clamp(0.0, 1.0);
+        self.base_fov + (self.max_fov - self.base_fov) * t
+    }
 }
 
 impl Default for CameraController {
     fn default() -> Self {
+        let presets = vec![
+            CameraMode::Normal,
+            CameraMode::Fixed {
+                position: Vec3::new(0.0, 40.0, -23.0),
+                look_target: Vec3::ZERO,
+            },
+            CameraMode::Orbit {
+                yaw: 0.0,
+                pitch: 0.3,
+                sensitivity: 0.003,
+            },
+        ];
+
         CameraController {
             z_distance: 10.0,
             y_distance: 7.0,
@@ -65,12 +133,16 @@ impl Default for CameraController {
             easing: 4.0,
             target_position: Vec3::ZERO,
             player_position: Vec3::ZERO,
-            // mode: CameraMode::Normal,
-            mode: CameraMode::Fixed {
-                position: Vec3::new(0.0, 40.0, -23.0),
-                look_target: Vec3::ZERO,
-            },
+            mode: presets[1],
+            presets,
+            preset_index: 1,
             blocked_by_a_wall: false,
+            base_fov: 0.785398,
+            max_fov: 1.2,
+            fov_momentum_range: (10.0, 20.0),
+            bob_phase: 0.0,
+            bob_amplitude: Vec2::ZERO,
+            bob_enabled: true,
         }
     }
 }
@@ -79,30 +151,125 @@ pub struct CameraControlPlugin;
 
 impl Plugin for CameraControlPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(update_camera_target_position)
+        app.init_resource::<CameraConfig>()
+            .add_startup_system(apply_player_values_to_camera)
+            .add_system(update_camera_target_position)
             .add_system(lerp_to_camera_position.after(update_camera_target_position))
             .add_system(rotate_camera)
-            .add_system(debug_change_camera_mode);
+            .add_system(orbit_camera)
+            .add_system(cycle_camera_preset)
+            .add_system(cycle_adjust_target)
+            .add_system(adjust_camera_config)
+            .add_system(update_camera_fov)
+            .add_system(apply_camera_headbob.after(lerp_to_camera_position));
+    }
+}
+
+fn apply_camera_headbob(
+    time: Res<Time>,
+    player_query: Query<(Option<&Grounded>, &Momentum), With<Player>>,
+    mut camera_query: Query<(&mut Transform, &mut CameraController)>,
+) {
+    let Ok((mut transform, mut camera)) = camera_query.get_single_mut() else { return; };
+    if !camera.bob_enabled {
+        return;
+    }
+    let Ok((grounded, momentum)) = player_query.get_single() else { return; };
+
+    let is_bobbing = grounded.is_some() && momentum.has_momentum();
+    let target_amplitude = if is_bobbing {
+        Vec2::new(0.06, 0.04)
+    } else {
+        Vec2::ZERO
+    };
+    camera.bob_amplitude = camera
+        .bob_amplitude
+        .lerp(target_amplitude, (time.delta_seconds() * 6.0).clamp(0.0, 1.0));
+
+    if is_bobbing {
+        camera.bob_phase += momentum.get() * time.delta_seconds();
+    }
+
+    let offset = Vec3::new(
+        camera.bob_phase.sin() * camera.bob_amplitude.x,
+        (camera.bob_phase * 2.0).sin().abs() * camera.bob_amplitude.y,
+        0.0,
+    );
+    transform.translation += transform.rotation * offset;
+}
+
+fn update_camera_fov(
+    time: Res<Time>,
+    camera_query: Query<&CameraController>,
+    player_query: Query<&Momentum, With<Player>>,
+    mut projection_query: Query<&mut Projection, With<MainCamera>>,
+) {
+    let camera = camera_query.single();
+    let Ok(momentum) = player_query.get_single() else { return; };
+    let Ok(mut projection) = projection_query.get_single_mut() else { return; };
+
+    if let Projection::Perspective(perspective) = projection.as_mut() {
+        let target_fov = camera.desired_fov(momentum.get());
+        let t = (time.delta_seconds() * camera.easing).clamp(0.0, 1.0);
+        perspective.fov += (target_fov - perspective.fov) * t;
     }
 }
 
-fn debug_change_camera_mode(
+fn cycle_camera_preset(
     mut camera_query: Query<&mut CameraController>,
     player_query: Query<&ActionState<PlayerAction>>,
 ) {
     let mut camera = camera_query.single_mut();
-    let Ok(player_action) = player_query.get_single() else {println!("No Player to set camera mode"); return;};
+    let Ok(player_action) = player_query.get_single() else {println!("No Player to cycle camera preset"); return;};
     if player_action.just_pressed(PlayerAction::CameraMode) {
-        if let CameraMode::Normal = camera.mode {
-            camera.mode = CameraMode::Fixed {
-                position: Vec3::new(0.0, 30.0, -20.0),
-                look_target: Vec3::ZERO,
-            };
-        } else {
-            camera.mode = CameraMode::Normal;
+        camera.preset_index = (camera.preset_index + 1) % camera.presets.len();
+        camera.mode = camera.presets[camera.preset_index];
+    }
+}
+
+fn cycle_adjust_target(
+    mut camera_config: ResMut<CameraConfig>,
+    player_query: Query<&ActionState<PlayerAction>>,
+) {
+    let Ok(player_action) = player_query.get_single() else {println!("No Player to cycle the adjust target"); return;};
+    if player_action.just_pressed(PlayerAction::CycleAdjustTarget) {
+        camera_config.adjusting = camera_config.adjusting.next();
+    }
+}
+
+fn adjust_camera_config(
+    camera_config: Res<CameraConfig>,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut camera_query: Query<&mut CameraController>,
+) {
+    let scroll: f32 = wheel_events.iter().map(|event| event.y).sum();
+    if scroll == 0.0 {
+        return;
+    }
+
+    let mut camera = camera_query.single_mut();
+    match camera_config.adjusting {
+        AdjustTarget::Zoom => camera.z_distance = (camera.z_distance + scroll).max(1.0),
+        AdjustTarget::Easing => camera.easing = (camera.easing + scroll * 0.1).max(0.1),
+        AdjustTarget::Height => camera.y_distance = (camera.y_distance + scroll).max(0.0),
+        AdjustTarget::Sensitivity => {
+            if let CameraMode::Orbit { sensitivity, .. } = &mut camera.mode {
+                *sensitivity = (*sensitivity + scroll * 0.0005).max(0.00005);
+            }
         }
     }
 }
+fn apply_player_values_to_camera(
+    values: Res<PlayerValuesState>,
+    mut camera_query: Query<&mut CameraController>,
+) {
+    if let Ok(mut camera) = camera_query.get_single_mut() {
+        camera.z_distance = values.camera_z_distance;
+        camera.y_distance = values.camera_y_distance;
+        camera.easing = values.camera_easing;
+    }
+}
+
 fn update_camera_target_position(
     rapier_context: Res<RapierContext>,
     mut camera_query: Query<&mut CameraController>,
@@ -111,14 +278,26 @@ fn update_camera_target_position(
     let mut camera = camera_query.single_mut();
     let (player_entity, player_transform, player_momentum) = player_query.single();
 
-    let mut starting_transform = player_transform.clone();
-    starting_transform.rotation = Quat::default();
-    starting_transform.rotate_y(camera.angle.to_radians());
-    let dir = starting_transform.forward().normalize();
     camera.player_position = player_transform.translation;
-    let mut desired_position = starting_transform.translation
-        + (dir * camera.desired_z_distance(player_momentum.get()))
-        + (Vec3::Y * camera.desired_y_height(player_momentum.get()));
+
+    let mut desired_position = if let CameraMode::Orbit { yaw, pitch, .. } = camera.mode {
+        let base_height = camera.desired_y_height(player_momentum.get());
+        let z_distance = camera.desired_z_distance(player_momentum.get());
+        let offset = Vec3::new(
+            z_distance * pitch.cos() * yaw.sin(),
+            z_distance * pitch.sin() + base_height,
+            z_distance * pitch.cos() * yaw.cos(),
+        );
+        player_transform.translation + offset
+    } else {
+        let mut starting_transform = player_transform.clone();
+        starting_transform.rotation = Quat::default();
+        starting_transform.rotate_y(camera.angle.to_radians());
+        let dir = starting_transform.forward().normalize();
+        starting_transform.translation
+            + (dir * camera.desired_z_distance(player_momentum.get()))
+            + (Vec3::Y * camera.desired_y_height(player_momentum.get()))
+    };
 
     let ray_pos = player_transform.translation;
     let ray_dir = (desired_position - player_transform.translation).normalize_or_zero();
@@ -163,10 +342,49 @@ fn lerp_to_camera_position(
                 transform.translation = lerped_position;
                 transform.look_at(look_target, Vec3::Y);
             }
+            CameraMode::Orbit { .. } => {
+                let lerped_position = transform.translation.lerp(
+                    camera.target_position,
+                    time.delta_seconds() * camera.desired_easing_speed(),
+                );
+                transform.translation = lerped_position;
+                transform.look_at(camera.player_position, Vec3::Y);
+            }
         }
     }
 }
 
+fn orbit_camera(
+    mut motion_events: EventReader<MouseMotion>,
+    mut windows: ResMut<Windows>,
+    mut camera_query: Query<&mut CameraController>,
+) {
+    let mut camera = camera_query.single_mut();
+    let Some(window) = windows.get_primary_mut() else {
+        motion_events.clear();
+        return;
+    };
+
+    let CameraMode::Orbit { yaw, pitch, sensitivity } = &mut camera.mode else {
+        motion_events.clear();
+        window.set_cursor_grab_mode(CursorGrabMode::None);
+        window.set_cursor_visibility(true);
+        return;
+    };
+
+    let mut delta = Vec2::ZERO;
+    for motion in motion_events.iter() {
+        delta += motion.delta;
+    }
+
+    *yaw += delta.x * *sensitivity;
+    *pitch -= delta.y * *sensitivity;
+    *pitch = pitch.clamp(-1.4, 1.4);
+
+    window.set_cursor_grab_mode(CursorGrabMode::Locked);
+    window.set_cursor_visibility(false);
+}
+
 fn rotate_camera(
     mut camera_query: Query<&mut CameraController>,
     player_query: Query<&ActionState<PlayerAction>>,
@@ -1,16 +1,59 @@
+use super::PlayerValuesState;
 use crate::{
-    DebugBall, Drift, Grounded, Landing, LedgeGrab, MainCamera, Momentum, Movement, OutsideForce,
-    Player, PlayerAction,
+    Busy, DebugBall, Drift, Grounded, Landing, LedgeGrab, MainCamera, Momentum, Movement,
+    OutsideForce, Player, PlayerAction,
 };
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
 
-const PLAYER_ROTATION_SPEED: f32 = 10.0;
-
 #[derive(Component)]
 pub struct Crouching;
 
+#[derive(Component)]
+pub struct Sprinting;
+
+#[derive(Component)]
+pub struct RotationSpeed(pub f32);
+
+impl RotationSpeed {
+    pub fn from_values(values: &PlayerValuesState) -> Self {
+        RotationSpeed(values.rotation_speed)
+    }
+}
+
+#[derive(Component)]
+pub struct SnapRotation;
+
+#[derive(Component)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    pub regen_rate: f32,
+}
+
+impl Stamina {
+    pub fn new(max: f32, regen_rate: f32) -> Self {
+        Stamina {
+            current: max,
+            max,
+            regen_rate,
+        }
+    }
+
+    pub fn drain(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+    }
+
+    pub fn regen(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
 #[derive(Resource)]
 pub struct PlayerSpeed {
     accel_timer: Timer,
@@ -25,9 +68,28 @@ pub struct PlayerSpeed {
 }
 
 impl PlayerSpeed {
-    pub fn reset(&mut self) {
-        self.current_speed = self.base_speed;
-        self.top_speed = self.base_top_speed;
+    pub fn from_values(values: &PlayerValuesState) -> Self {
+        PlayerSpeed {
+            accel_timer: Timer::from_seconds(values.accel_timer_seconds, TimerMode::Once),
+            decel_timer: Timer::from_seconds(values.decel_timer_seconds, TimerMode::Once),
+            base_speed: values.base_speed,
+            crawl_speed: values.crawl_speed,
+            current_speed: values.base_speed,
+            top_speed: values.base_top_speed,
+            base_top_speed: values.base_top_speed,
+            acceleration: values.acceleration,
+            deceleration: values.deceleration,
+        }
+    }
+
+    pub fn reset(&mut self, values: &PlayerValuesState) {
+        self.base_speed = values.base_speed;
+        self.crawl_speed = values.crawl_speed;
+        self.base_top_speed = values.base_top_speed;
+        self.acceleration = values.acceleration;
+        self.deceleration = values.deceleration;
+        self.current_speed = values.base_speed;
+        self.top_speed = values.base_top_speed;
         self.accel_timer.reset();
         self.decel_timer.reset();
     }
@@ -58,6 +120,17 @@ impl PlayerSpeed {
         self.current_speed
     }
 
+    pub fn adjust_top_speed(&mut self, delta: std::time::Duration, seconds: f32, top_speed: f32) {
+        self.top_speed = top_speed;
+        if self.current_speed > self.top_speed {
+            self.decel_timer.tick(delta);
+            if self.decel_timer.finished() {
+                self.current_speed +=
+                    (self.top_speed - self.current_speed) * (seconds * self.deceleration);
+            }
+        }
+    }
+
     pub fn set(&mut self, speed: f32) {
         self.top_speed = speed;
         self.current_speed = speed;
@@ -66,17 +139,7 @@ impl PlayerSpeed {
 
 impl Default for PlayerSpeed {
     fn default() -> Self {
-        PlayerSpeed {
-            accel_timer: Timer::from_seconds(0.3, TimerMode::Once),
-            decel_timer: Timer::from_seconds(0.5, TimerMode::Once),
-            base_speed: 7.5,
-            crawl_speed: 4.0,
-            current_speed: 7.5,
-            top_speed: 15.0,
-            base_top_speed: 15.0,
-            acceleration: 1.0,
-            deceleration: 2.0,
-        }
+        PlayerSpeed::from_values(&PlayerValuesState::default())
     }
 }
 
@@ -144,35 +207,62 @@ pub fn get_direction_in_camera_space(
 
 pub fn rotate_to_direction(
     time: Res<Time>,
-    mut query: Query<(&mut Transform, &Movement, Option<&Landing>), (With<Player>, With<Grounded>)>,
+    mut query: Query<
+        (
+            &mut Transform,
+            &Movement,
+            &RotationSpeed,
+            Option<&Landing>,
+            Option<&SnapRotation>,
+        ),
+        (With<Player>, With<Grounded>),
+    >,
     mut rotation_target: Local<Transform>,
 ) {
-    for (mut transform, direction, is_landing) in &mut query {
+    for (mut transform, direction, rotation_speed, is_landing, snap) in &mut query {
         rotation_target.translation = transform.translation;
-        let flat_velo_direction = Vec3::new(direction.0.x, 0.0, direction.0.z).normalize_or_zero();
+        let mut flat_velo_direction =
+            Vec3::new(direction.0.x, 0.0, direction.0.z).normalize_or_zero();
         if flat_velo_direction != Vec3::ZERO {
-            let target_position = rotation_target.translation + flat_velo_direction;
+            if snap.is_some() {
+                flat_velo_direction = snap_to_compass(flat_velo_direction);
+            }
 
+            let target_position = rotation_target.translation + flat_velo_direction;
             rotation_target.look_at(target_position, Vec3::Y);
-            let turn_speed = if is_landing.is_some() {
-                PLAYER_ROTATION_SPEED * 2.0
+
+            if snap.is_some() {
+                transform.rotation = rotation_target.rotation;
             } else {
-                PLAYER_ROTATION_SPEED
-            };
+                let turn_speed = if is_landing.is_some() {
+                    rotation_speed.0 * 2.0
+                } else {
+                    rotation_speed.0
+                };
 
-            transform.rotation = transform
-                .rotation
-                .slerp(rotation_target.rotation, time.delta_seconds() * turn_speed);
+                transform.rotation = transform
+                    .rotation
+                    .slerp(rotation_target.rotation, time.delta_seconds() * turn_speed);
+            }
         }
     }
 }
 
+fn snap_to_compass(direction: Vec3) -> Vec3 {
+    const COMPASS_STEP: f32 = std::f32::consts::FRAC_PI_4;
+
+    let angle = direction.z.atan2(direction.x);
+    let snapped_angle = (angle / COMPASS_STEP).round() * COMPASS_STEP;
+    Vec3::new(snapped_angle.cos(), 0.0, snapped_angle.sin())
+}
+
 pub fn handle_player_speed(
     time: Res<Time>,
+    values: Res<PlayerValuesState>,
     mut player_speed: ResMut<PlayerSpeed>,
     mut query: Query<
         (&mut Momentum, &Movement, &ActionState<PlayerAction>),
-        (With<Player>, With<Grounded>, Without<Crouching>),
+        (With<Player>, With<Grounded>, Without<Crouching>, Without<Busy>),
     >,
 ) {
     for (mut momentum, movement, action) in &mut query {
@@ -185,11 +275,82 @@ pub fn handle_player_speed(
             momentum.set(player_speed.current_speed);
         } else {
             momentum.reset();
-            player_speed.reset();
+            player_speed.reset(&values);
         }
     }
 }
 
+pub fn handle_sprint(
+    time: Res<Time>,
+    values: Res<PlayerValuesState>,
+    mut player_speed: ResMut<PlayerSpeed>,
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &mut Stamina,
+            &ActionState<PlayerAction>,
+            Option<&Sprinting>,
+        ),
+        (With<Player>, With<Grounded>),
+    >,
+) {
+    for (entity, mut stamina, action, sprinting) in &mut query {
+        let wants_to_sprint = action.pressed(PlayerAction::Sprint) && !stamina.is_empty();
+
+        if wants_to_sprint {
+            if sprinting.is_none() {
+                commands.entity(entity).insert(Sprinting);
+            }
+            stamina.drain(values.sprint_stamina_drain_rate * time.delta_seconds());
+            let target_top_speed = if stamina.is_empty() {
+                commands.entity(entity).remove::<Sprinting>();
+                values.base_top_speed
+            } else {
+                values.base_top_speed * values.sprint_speed_multiplier
+            };
+            player_speed.adjust_top_speed(time.delta(), time.delta_seconds(), target_top_speed);
+        } else {
+            if sprinting.is_some() {
+                commands.entity(entity).remove::<Sprinting>();
+            }
+            player_speed.adjust_top_speed(time.delta(), time.delta_seconds(), values.base_top_speed);
+        }
+    }
+}
+
+pub fn handle_dash(
+    values: Res<PlayerValuesState>,
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &mut Stamina,
+            &mut Momentum,
+            &Movement,
+            &ActionState<PlayerAction>,
+        ),
+        (With<Player>, Without<Busy>),
+    >,
+) {
+    for (entity, mut stamina, mut momentum, movement, action) in &mut query {
+        if !action.just_pressed(PlayerAction::Dash) || stamina.current < values.dash_stamina_cost {
+            continue;
+        }
+
+        let flat_direction = Vec3::new(movement.0.x, 0.0, movement.0.z).normalize_or_zero();
+        if flat_direction == Vec3::ZERO {
+            continue;
+        }
+
+        stamina.drain(values.dash_stamina_cost);
+        momentum.set(momentum.get() + values.dash_impulse_strength);
+        commands
+            .entity(entity)
+            .insert(Busy::new(values.dash_cooldown_seconds));
+    }
+}
+
 pub fn apply_momentum(
     mut query: Query<
         (
@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 pub mod locomotion;
 pub use locomotion::*;
@@ -32,8 +33,8 @@ impl Busy {
 pub struct Landing(Timer);
 
 impl Landing {
-    pub fn new() -> Self {
-        Landing(Timer::from_seconds(0.15, TimerMode::Once))
+    pub fn new(seconds: f32) -> Self {
+        Landing(Timer::from_seconds(seconds, TimerMode::Once))
     }
 
     pub fn tick(&mut self, duration: Duration) {
@@ -45,14 +46,122 @@ impl Landing {
     }
 }
 
+#[derive(Resource, Serialize, Deserialize, Clone)]
+pub struct PlayerValuesState {
+    pub rotation_speed: f32,
+    pub base_speed: f32,
+    pub crawl_speed: f32,
+    pub base_top_speed: f32,
+    pub acceleration: f32,
+    pub deceleration: f32,
+    pub accel_timer_seconds: f32,
+    pub decel_timer_seconds: f32,
+    pub landing_seconds: f32,
+    pub camera_z_distance: f32,
+    pub camera_y_distance: f32,
+    pub camera_easing: f32,
+    pub stamina_max: f32,
+    pub stamina_regen_rate: f32,
+    pub sprint_stamina_drain_rate: f32,
+    pub sprint_speed_multiplier: f32,
+    pub dash_stamina_cost: f32,
+    pub dash_cooldown_seconds: f32,
+    pub dash_impulse_strength: f32,
+}
+
+impl Default for PlayerValuesState {
+    fn default() -> Self {
+        PlayerValuesState {
+            rotation_speed: 10.0,
+            base_speed: 7.5,
+            crawl_speed: 4.0,
+            base_top_speed: 15.0,
+            acceleration: 1.0,
+            deceleration: 2.0,
+            accel_timer_seconds: 0.3,
+            decel_timer_seconds: 0.5,
+            landing_seconds: 0.15,
+            camera_z_distance: 10.0,
+            camera_y_distance: 7.0,
+            camera_easing: 4.0,
+            stamina_max: 100.0,
+            stamina_regen_rate: 20.0,
+            sprint_stamina_drain_rate: 25.0,
+            sprint_speed_multiplier: 1.6,
+            dash_stamina_cost: 30.0,
+            dash_cooldown_seconds: 0.5,
+            dash_impulse_strength: 12.0,
+        }
+    }
+}
+
+impl PlayerValuesState {
+    const ASSET_PATH: &'static str = "assets/config/player_values.ron";
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::ASSET_PATH)
+            .ok()
+            .and_then(|contents| ron::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+}
+
 pub struct PlayerMovementPlugin;
 
 impl Plugin for PlayerMovementPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(PlayerLocomotionPlugin)
+        app.insert_resource(PlayerValuesState::load())
+            .add_plugin(PlayerLocomotionPlugin)
             .add_plugin(PlayerJumpingPlugin)
             .add_system(handle_landing)
-            .add_system(handle_busy);
+            .add_system(detect_landing)
+            .add_system(handle_busy)
+            .add_system(regen_stamina)
+            .add_system(attach_rotation_speed)
+            .add_system(attach_stamina)
+            .add_system(handle_sprint)
+            .add_system(handle_dash);
+    }
+}
+
+pub fn attach_stamina(
+    mut commands: Commands,
+    values: Res<PlayerValuesState>,
+    query: Query<Entity, (With<Player>, Without<Stamina>)>,
+) {
+    for entity in &query {
+        commands
+            .entity(entity)
+            .insert(Stamina::new(values.stamina_max, values.stamina_regen_rate));
+    }
+}
+
+pub fn attach_rotation_speed(
+    mut commands: Commands,
+    values: Res<PlayerValuesState>,
+    query: Query<Entity, (With<Player>, Without<RotationSpeed>)>,
+) {
+    for entity in &query {
+        commands
+            .entity(entity)
+            .insert(RotationSpeed::from_values(&values));
+    }
+}
+
+pub fn regen_stamina(
+    time: Res<Time>,
+    values: Res<PlayerValuesState>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Stamina, Option<&Sprinting>), (With<Player>, With<Grounded>)>,
+) {
+    for (entity, mut stamina, sprinting) in &mut query {
+        if sprinting.is_none() {
+            stamina.regen(values.stamina_regen_rate * time.delta_seconds());
+        }
+
+        if sprinting.is_some() && stamina.is_empty() {
+            commands.entity(entity).remove::<Sprinting>();
+        }
     }
 }
 
@@ -65,6 +174,18 @@ pub fn handle_busy(mut commands: Commands, time: Res<Time>, mut query: Query<(En
     }
 }
 
+pub fn detect_landing(
+    mut commands: Commands,
+    values: Res<PlayerValuesState>,
+    query: Query<Entity, (With<Player>, Added<Grounded>, Without<Landing>)>,
+) {
+    for entity in &query {
+        commands
+            .entity(entity)
+            .insert(Landing::new(values.landing_seconds));
+    }
+}
+
 pub fn handle_landing(
     mut commands: Commands,
     time: Res<Time>,